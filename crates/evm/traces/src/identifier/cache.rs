@@ -0,0 +1,171 @@
+use super::SourceCodeLanguage;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, map::HashMap};
+use foundry_config::Chain;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Key under which an identified address is cached: the chain it was identified on (if any)
+/// and its address.
+type CacheKey = (Option<Chain>, Address);
+
+/// A cached identification result for a single address, along with the time it was fetched.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    label: Option<String>,
+    contract: Option<String>,
+    abi: Option<JsonAbi>,
+    language: Option<SourceCodeLanguage>,
+    fetched_at: u64,
+}
+
+/// A previously identified address as read back from the cache.
+pub struct CachedAddress {
+    pub label: Option<String>,
+    pub contract: Option<String>,
+    pub abi: Option<JsonAbi>,
+    pub language: Option<SourceCodeLanguage>,
+}
+
+/// Persistent, TTL-aware on-disk cache of [`IdentifiedAddress`](super::IdentifiedAddress) data,
+/// mirroring how [`SignaturesCache`](super::SignaturesCache) persists signatures.
+///
+/// This avoids re-hitting Etherscan/Sourcify for contracts that were already identified in a
+/// previous `forge test` / `cast run` invocation.
+///
+/// Verified source trees (see `IdentifiedAddress::sources`) are intentionally not persisted
+/// here; they're large and only needed by callers that opted into `with_sources(true)`.
+#[derive(Debug)]
+pub struct IdentifiedAddressCache {
+    /// Path to the JSON file backing this cache.
+    path: PathBuf,
+    /// How long a cached entry remains valid for.
+    ttl: Duration,
+    /// In-memory view of the cache, loaded from `path` on construction.
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Set once an entry is inserted or invalidated, so we only write back when needed.
+    dirty: bool,
+}
+
+impl IdentifiedAddressCache {
+    /// Loads (or creates) a cache backed by a JSON file at `path`, with the given entry TTL.
+    pub fn load(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let path = path.into();
+        let entries: Vec<(CacheKey, CacheEntry)> = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self { path, ttl, entries: entries.into_iter().collect(), dirty: false }
+    }
+
+    /// Returns the cached entry for `(chain, address)`, if present and not yet expired.
+    ///
+    /// An expired entry is evicted on read.
+    pub fn get(&mut self, chain: Option<Chain>, address: Address) -> Option<CachedAddress> {
+        let key = (chain, address);
+        let entry = self.entries.get(&key)?;
+        if Self::now().saturating_sub(entry.fetched_at) > self.ttl.as_secs() {
+            self.entries.remove(&key);
+            self.dirty = true;
+            return None;
+        }
+        let entry = self.entries.get(&key)?;
+        Some(CachedAddress {
+            label: entry.label.clone(),
+            contract: entry.contract.clone(),
+            abi: entry.abi.clone(),
+            language: entry.language,
+        })
+    }
+
+    /// Inserts or overwrites the cached entry for `(chain, address)`.
+    pub fn insert(
+        &mut self,
+        chain: Option<Chain>,
+        address: Address,
+        label: Option<String>,
+        contract: Option<String>,
+        abi: Option<JsonAbi>,
+        language: Option<SourceCodeLanguage>,
+    ) {
+        self.entries.insert(
+            (chain, address),
+            CacheEntry { label, contract, abi, language, fetched_at: Self::now() },
+        );
+        self.dirty = true;
+    }
+
+    /// Forces the entry for `(chain, address)` to be treated as missing on the next [`Self::get`],
+    /// regardless of its TTL.
+    pub fn invalidate(&mut self, chain: Option<Chain>, address: Address) {
+        if self.entries.remove(&(chain, address)).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Persists the cache to disk if it has changed since the last save.
+    pub fn save(&mut self) -> eyre::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // `serde_json` can't serialize a map with a non-string key, so round-trip through a
+        // `Vec` of entries instead.
+        let entries: Vec<(&CacheKey, &CacheEntry)> = self.entries.iter().collect();
+        std::fs::write(&self.path, serde_json::to_vec(&entries)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    }
+}
+
+impl Drop for IdentifiedAddressCache {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+/// Default path, under `root`, of the JSON file Etherscan/Sourcify identification results are
+/// cached in.
+pub fn default_cache_path(root: impl AsRef<Path>) -> PathBuf {
+    root.as_ref().join("identified-addresses.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identified-addresses.json");
+        let address = Address::random();
+
+        let mut cache = IdentifiedAddressCache::load(&path, Duration::from_secs(3600));
+        cache.insert(
+            Some(Chain::mainnet()),
+            address,
+            Some("Token".to_string()),
+            Some("Token.sol:Token".to_string()),
+            None,
+            Some(SourceCodeLanguage::Solidity),
+        );
+        cache.save().unwrap();
+        drop(cache);
+
+        assert!(path.exists(), "cache file should have been written");
+
+        let mut reloaded = IdentifiedAddressCache::load(&path, Duration::from_secs(3600));
+        let hit = reloaded.get(Some(Chain::mainnet()), address).expect("cache hit after reload");
+        assert_eq!(hit.label.as_deref(), Some("Token"));
+        assert_eq!(hit.language, Some(SourceCodeLanguage::Solidity));
+    }
+}
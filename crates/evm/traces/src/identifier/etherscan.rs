@@ -0,0 +1,134 @@
+use super::{IdentifiedAddress, SourceCodeLanguage, SourceTree, TraceIdentifier};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, map::HashMap};
+use foundry_block_explorers::Client;
+use foundry_config::{Chain, Config};
+use revm_inspectors::tracing::types::CallTraceNode;
+use std::borrow::Cow;
+
+/// A [`TraceIdentifier`] that queries Etherscan (or a compatible block explorer) for verified
+/// contract metadata.
+#[derive(Debug)]
+pub struct EtherscanIdentifier {
+    /// The Etherscan client used to fetch contract metadata.
+    client: Client,
+    /// Cache of already fetched (or failed) addresses, so repeated lookups within a run don't
+    /// re-hit the network.
+    contracts: HashMap<Address, Option<EtherscanMetadata>>,
+    /// Whether to additionally fetch the full verified source tree for each address.
+    fetch_sources: bool,
+}
+
+#[derive(Clone, Debug)]
+struct EtherscanMetadata {
+    abi: JsonAbi,
+    /// Path to the compiled source file, e.g. `"src/Token.sol"`.
+    artifact_path: String,
+    contract_name: String,
+    compiler_version: String,
+    language: Option<SourceCodeLanguage>,
+    sources: Option<HashMap<String, String>>,
+}
+
+impl EtherscanIdentifier {
+    /// Creates a new Etherscan identifier for the given chain, using the Etherscan API key (if
+    /// any) configured for it.
+    ///
+    /// Returns `None` if `chain` could not be resolved from `config`, mirroring
+    /// [`SourcifyIdentifier::new`](super::SourcifyIdentifier::new).
+    pub fn new(config: &Config, chain: Option<Chain>) -> eyre::Result<Option<Self>> {
+        let Some(chain) = chain.or(config.chain) else { return Ok(None) };
+        let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
+        let client = Client::builder().chain(chain)?.with_api_key(api_key).build()?;
+        Ok(Some(Self { client, contracts: HashMap::default(), fetch_sources: false }))
+    }
+
+    /// Toggles fetching the full verified source tree alongside the ABI and label.
+    pub fn set_fetch_sources(&mut self, fetch_sources: bool) {
+        self.fetch_sources = fetch_sources;
+    }
+
+    fn fetch(&mut self, address: Address) -> Option<EtherscanMetadata> {
+        if let Some(entry) = self.contracts.get(&address) {
+            return entry.clone();
+        }
+
+        let fetch_sources = self.fetch_sources;
+        let metadata = foundry_common::block_on(self.client.contract_source_code(address))
+            .ok()
+            .and_then(|mut meta| meta.items.pop())
+            .and_then(|item| {
+                let abi: JsonAbi = item.abi().ok()?;
+                // Etherscan reports Vyper contracts via a `"vyper:<version>"` compiler version
+                // string; everything else verified through the standard Solidity pipeline.
+                let language = if item.compiler_version.starts_with("vyper") {
+                    Some(SourceCodeLanguage::Vyper)
+                } else {
+                    Some(SourceCodeLanguage::Solidity)
+                };
+                // Handles the single-file, flattened, and standard-JSON-input verified source
+                // shapes transparently.
+                let tree = item.source_tree();
+                // Use the entry whose file stem matches the contract name (the file that was
+                // actually compiled as the target) as the artifact segment of the
+                // `"<artifact>:<contract>"` identifier, mirroring `SourcifyIdentifier`. Falls back
+                // to the first entry, then to the contract name itself, if the source tree is
+                // empty or no entry matches.
+                let artifact_path = tree
+                    .entries
+                    .iter()
+                    .find(|entry| {
+                        entry.path.file_stem().is_some_and(|stem| stem == item.contract_name.as_str())
+                    })
+                    .or_else(|| tree.entries.first())
+                    .map(|entry| entry.path.display().to_string())
+                    .unwrap_or_else(|| item.contract_name.clone());
+                let sources = fetch_sources.then(|| {
+                    tree.entries
+                        .iter()
+                        .map(|entry| {
+                            (entry.path.display().to_string(), entry.contents.clone())
+                        })
+                        .collect()
+                });
+                Some(EtherscanMetadata {
+                    abi,
+                    artifact_path,
+                    contract_name: item.contract_name,
+                    compiler_version: item.compiler_version,
+                    language,
+                    sources,
+                })
+            });
+
+        self.contracts.insert(address, metadata.clone());
+        metadata
+    }
+}
+
+impl TraceIdentifier for EtherscanIdentifier {
+    fn identify_addresses(&mut self, nodes: &[&CallTraceNode]) -> Vec<IdentifiedAddress<'_>> {
+        nodes
+            .iter()
+            .filter_map(|node| {
+                let address = node.trace.address;
+                let metadata = self.fetch(address)?;
+                let contract = format!("{}:{}", metadata.artifact_path, metadata.contract_name);
+                let sources = metadata.sources.map(|sources| SourceTree {
+                    sources,
+                    compiler_version: Some(metadata.compiler_version.clone()),
+                    settings: None,
+                });
+                Some(IdentifiedAddress {
+                    address,
+                    label: Some(metadata.contract_name.clone()),
+                    contract: Some(contract),
+                    abi: Some(Cow::Owned(metadata.abi)),
+                    artifact_id: None,
+                    language: metadata.language,
+                    sources,
+                })
+            })
+            .collect()
+    }
+}
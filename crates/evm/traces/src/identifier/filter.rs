@@ -0,0 +1,113 @@
+use alloy_primitives::{Address, map::HashSet};
+use revm_inspectors::tracing::types::{CallKind, CallTraceNode};
+use std::ops::RangeBounds;
+
+/// Filters [`CallTraceNode`]s before they are handed to a [`TraceIdentifier`](super::TraceIdentifier),
+/// so identification only runs on the subset of a trace the caller cares about.
+///
+/// This avoids expensive Etherscan/Sourcify lookups for addresses that will be discarded anyway
+/// in large traces, and lets tooling focus identification on, say, only external top-level calls.
+#[derive(Clone, Debug)]
+pub struct TraceFilter {
+    /// If non-empty, only nodes whose address is in this set are included.
+    addresses: HashSet<Address>,
+    /// Addresses excluded regardless of `addresses`.
+    excluded_addresses: HashSet<Address>,
+    /// The minimum call depth a node must be at to be included.
+    min_depth: usize,
+    /// If non-empty, only nodes of one of these call types are included.
+    call_kinds: HashSet<CallKind>,
+    /// The range of node indices (position within the trace arena) to include.
+    index_range: (usize, usize),
+}
+
+impl Default for TraceFilter {
+    /// Matches every node, same as [`TraceFilter::new`].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceFilter {
+    /// Creates a filter that matches every node.
+    pub fn new() -> Self {
+        Self {
+            addresses: HashSet::default(),
+            excluded_addresses: HashSet::default(),
+            min_depth: 0,
+            call_kinds: HashSet::default(),
+            index_range: (0, usize::MAX),
+        }
+    }
+
+    /// Restricts the filter to only the given addresses.
+    pub fn with_addresses(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.addresses = addresses.into_iter().collect();
+        self
+    }
+
+    /// Excludes the given addresses, regardless of [`Self::with_addresses`].
+    pub fn exclude_addresses(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.excluded_addresses = addresses.into_iter().collect();
+        self
+    }
+
+    /// Only includes nodes at or above this call depth.
+    pub fn with_min_depth(mut self, min_depth: usize) -> Self {
+        self.min_depth = min_depth;
+        self
+    }
+
+    /// Restricts the filter to the given call types (e.g. `CALL`, `DELEGATECALL`, `CREATE`).
+    pub fn with_call_kinds(mut self, kinds: impl IntoIterator<Item = CallKind>) -> Self {
+        self.call_kinds = kinds.into_iter().collect();
+        self
+    }
+
+    /// Restricts the filter to nodes whose index falls within `range`.
+    pub fn with_index_range(mut self, range: impl RangeBounds<usize>) -> Self {
+        let start = match range.start_bound() {
+            std::ops::Bound::Included(&s) => s,
+            std::ops::Bound::Excluded(&s) => s + 1,
+            std::ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            std::ops::Bound::Included(&e) => Some(e),
+            std::ops::Bound::Excluded(&e) => e.checked_sub(1),
+            std::ops::Bound::Unbounded => Some(usize::MAX),
+        };
+        // An exclusive end of `0` contains no index; `start > end` makes every node fail to match.
+        self.index_range = match end {
+            Some(end) => (start, end),
+            None => (1, 0),
+        };
+        self
+    }
+
+    /// Returns `true` if `node` matches this filter.
+    fn matches(&self, node: &CallTraceNode) -> bool {
+        let address = node.trace.address;
+        if self.excluded_addresses.contains(&address) {
+            return false;
+        }
+        if !self.addresses.is_empty() && !self.addresses.contains(&address) {
+            return false;
+        }
+        if node.trace.depth < self.min_depth as u64 {
+            return false;
+        }
+        if !self.call_kinds.is_empty() && !self.call_kinds.contains(&node.trace.kind) {
+            return false;
+        }
+        // `node.idx` is the node's position in the trace arena, which is stable regardless of
+        // which subset of nodes this filter is applied to; matching against slice position
+        // instead would make the range mean something different for every caller.
+        node.idx >= self.index_range.0 && node.idx <= self.index_range.1
+    }
+
+    /// Selects the subset of `nodes` matching this filter.
+    pub fn filter<'a>(&self, nodes: &[&'a CallTraceNode]) -> Vec<&'a CallTraceNode> {
+        nodes.iter().copied().filter(|node| self.matches(node)).collect()
+    }
+}
+
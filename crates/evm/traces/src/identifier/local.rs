@@ -0,0 +1,64 @@
+use super::{IdentifiedAddress, SourceCodeLanguage, TraceIdentifier};
+use alloy_primitives::{Address, Bytes, map::HashMap};
+use foundry_common::ContractsByArtifact;
+use revm_inspectors::tracing::types::CallTraceNode;
+use std::borrow::Cow;
+
+/// A [`TraceIdentifier`] that identifies addresses using the bytecode of contracts compiled in
+/// the current project.
+#[derive(Clone, Debug)]
+pub struct LocalTraceIdentifier<'a> {
+    /// Known contracts, by artifact.
+    known_contracts: &'a ContractsByArtifact,
+    /// Maps an address to the bytecode deployed at it. An address is identified only if its entry
+    /// here exactly equals the `bytecode` of one of `known_contracts`.
+    contracts_bytecode: Option<&'a HashMap<Address, Bytes>>,
+}
+
+impl<'a> LocalTraceIdentifier<'a> {
+    /// Creates a new local identifier backed by `known_contracts`.
+    ///
+    /// Without [`Self::with_bytecodes`] it identifies no address.
+    pub fn new(known_contracts: &'a ContractsByArtifact) -> Self {
+        Self { known_contracts, contracts_bytecode: None }
+    }
+
+    /// Sets the per-address bytecode that addresses are matched against: an address is
+    /// identified as the first known contract whose `bytecode` equals its entry here, and
+    /// addresses without an entry are not identified.
+    pub fn with_bytecodes(mut self, contracts_bytecode: &'a HashMap<Address, Bytes>) -> Self {
+        self.contracts_bytecode = Some(contracts_bytecode);
+        self
+    }
+}
+
+impl TraceIdentifier for LocalTraceIdentifier<'_> {
+    fn identify_addresses(&mut self, nodes: &[&CallTraceNode]) -> Vec<IdentifiedAddress<'_>> {
+        nodes
+            .iter()
+            .filter_map(|node| {
+                let address = node.trace.address;
+                let code = self.contracts_bytecode.and_then(|map| map.get(&address))?;
+                let (artifact_id, contract) = self.known_contracts.iter().find(|(_, contract)| {
+                    contract.bytecode.as_ref().is_some_and(|b| b.as_ref() == code.as_ref())
+                })?;
+
+                Some(IdentifiedAddress {
+                    address,
+                    label: Some(artifact_id.name.clone()),
+                    contract: Some(format!(
+                        "{}:{}",
+                        artifact_id.source.display(),
+                        artifact_id.name
+                    )),
+                    abi: Some(Cow::Borrowed(&contract.abi)),
+                    artifact_id: Some(artifact_id.clone()),
+                    // Inferred from the artifact's source file extension, since known_contracts
+                    // doesn't carry an explicit language tag.
+                    language: SourceCodeLanguage::from_artifact_id(artifact_id),
+                    sources: None,
+                })
+            })
+            .collect()
+    }
+}
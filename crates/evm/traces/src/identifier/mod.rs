@@ -1,10 +1,13 @@
 use alloy_json_abi::JsonAbi;
-use alloy_primitives::{Address, Bytes, map::HashMap};
+use alloy_primitives::{
+    Address, Bytes,
+    map::{HashMap, HashSet},
+};
 use foundry_common::ContractsByArtifact;
 use foundry_compilers::ArtifactId;
 use foundry_config::{Chain, Config};
 use revm_inspectors::tracing::types::CallTraceNode;
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 mod local;
 pub use local::LocalTraceIdentifier;
@@ -15,6 +18,15 @@ pub use etherscan::EtherscanIdentifier;
 mod signatures;
 pub use signatures::{SignaturesCache, SignaturesIdentifier};
 
+mod sourcify;
+pub use sourcify::SourcifyIdentifier;
+
+mod cache;
+pub use cache::{CachedAddress, IdentifiedAddressCache};
+
+mod filter;
+pub use filter::TraceFilter;
+
 /// An address identified by a [`TraceIdentifier`].
 pub struct IdentifiedAddress<'a> {
     /// The address.
@@ -29,6 +41,50 @@ pub struct IdentifiedAddress<'a> {
     pub abi: Option<Cow<'a, JsonAbi>>,
     /// The artifact ID of the contract, if any.
     pub artifact_id: Option<ArtifactId>,
+    /// The source language of the contract, if known.
+    pub language: Option<SourceCodeLanguage>,
+    /// The full verified source tree for the contract, if fetched.
+    ///
+    /// Only populated when the identifier that produced this address has been configured to
+    /// fetch sources (see [`TraceIdentifiers::with_sources`]), since doing so is considerably
+    /// more expensive than fetching just the ABI.
+    pub sources: Option<SourceTree>,
+}
+
+/// A reconstructed tree of verified contract sources, as returned by block explorers and
+/// Sourcify for single-file, flattened, and standard-JSON-input verified contracts alike.
+#[derive(Clone, Debug)]
+pub struct SourceTree {
+    /// Source file path (relative to the compilation root) mapped to its contents.
+    pub sources: HashMap<String, String>,
+    /// The compiler version the contract was verified with, if known (e.g. `"v0.8.23+commit.f704f362"`).
+    pub compiler_version: Option<String>,
+    /// The compiler settings used for verification (optimizer, EVM version, remappings, etc.),
+    /// if known.
+    pub settings: Option<serde_json::Value>,
+}
+
+/// The source language a verified/compiled contract was written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SourceCodeLanguage {
+    /// The contract was written in Solidity.
+    Solidity,
+    /// The contract was written in Vyper.
+    Vyper,
+}
+
+impl SourceCodeLanguage {
+    /// Infers the language from an artifact's source file extension (`.sol` or `.vy`).
+    ///
+    /// Used by [`LocalTraceIdentifier`] to tag locally compiled contracts, which otherwise have
+    /// no explicit language metadata attached.
+    pub fn from_artifact_id(artifact_id: &ArtifactId) -> Option<Self> {
+        match artifact_id.source.extension()?.to_str()? {
+            "sol" => Some(Self::Solidity),
+            "vy" => Some(Self::Vyper),
+            _ => None,
+        }
+    }
 }
 
 /// Trace identifiers figure out what ABIs and labels belong to all the addresses of the trace.
@@ -41,8 +97,24 @@ pub trait TraceIdentifier {
 pub struct TraceIdentifiers<'a> {
     /// The local trace identifier.
     pub local: Option<LocalTraceIdentifier<'a>>,
-    /// The optional Etherscan trace identifier.
+    /// The optional Etherscan trace identifier, used when no more specific per-chain
+    /// identifier applies to a node.
     pub etherscan: Option<EtherscanIdentifier>,
+    /// Per-chain Etherscan identifiers, used to correctly label traces that span multiple
+    /// chains (e.g. multi-fork scripts).
+    pub etherscan_chains: HashMap<Chain, EtherscanIdentifier>,
+    /// Resolves the chain a node's address belongs to, for routing to `etherscan_chains`.
+    ///
+    /// Nodes for which this returns `None` (or if this is unset) fall back to `etherscan`.
+    pub chain_resolver: Option<Box<dyn Fn(&CallTraceNode) -> Option<Chain> + 'a>>,
+    /// The optional Sourcify trace identifier.
+    pub sourcify: Option<SourcifyIdentifier>,
+    /// Persistent on-disk cache of previously identified addresses, consulted before hitting
+    /// Etherscan or Sourcify.
+    pub cache: Option<IdentifiedAddressCache>,
+    /// Whether to fetch the full verified source tree for remote addresses, gated behind a
+    /// separate toggle since it costs considerably more network/IO than ABI + label alone.
+    pub fetch_sources: bool,
 }
 
 impl Default for TraceIdentifiers<'_> {
@@ -54,23 +126,159 @@ impl Default for TraceIdentifiers<'_> {
 impl TraceIdentifier for TraceIdentifiers<'_> {
     fn identify_addresses(&mut self, nodes: &[&CallTraceNode]) -> Vec<IdentifiedAddress<'_>> {
         let mut identities = Vec::with_capacity(nodes.len());
+        // Tracks addresses already identified by an earlier stage, so later stages only spend a
+        // network round-trip on (and only emit one identity for) addresses still unresolved.
+        let mut resolved: HashSet<Address> = HashSet::default();
+
         if let Some(local) = &mut self.local {
             identities.extend(local.identify_addresses(nodes));
-            if identities.len() >= nodes.len() {
+            resolved.extend(identities.iter().map(|i| i.address));
+            if resolved.len() >= nodes.len() {
                 return identities;
             }
         }
-        if let Some(etherscan) = &mut self.etherscan {
-            identities.extend(etherscan.identify_addresses(nodes));
+
+        if !self.etherscan_chains.is_empty() || self.etherscan.is_some() {
+            let remaining = remaining_nodes(nodes, &resolved);
+            identities.extend(self.identify_addresses_etherscan(&remaining));
+            resolved.extend(identities.iter().map(|i| i.address));
+            if resolved.len() >= nodes.len() {
+                return identities;
+            }
+        }
+
+        if let Some(sourcify) = &mut self.sourcify {
+            let remaining = remaining_nodes(nodes, &resolved);
+            let chain = Some(sourcify.chain());
+            let mut cache = self.cache.take();
+            identities.extend(identify_with_cache(
+                &remaining,
+                cache.as_mut(),
+                |_| chain,
+                |nodes| sourcify.identify_addresses(nodes),
+            ));
+            self.cache = cache;
         }
         identities
     }
 }
 
+/// Returns the subset of `nodes` whose address isn't already in `resolved`.
+fn remaining_nodes<'b>(
+    nodes: &[&'b CallTraceNode],
+    resolved: &HashSet<Address>,
+) -> Vec<&'b CallTraceNode> {
+    nodes.iter().copied().filter(|node| !resolved.contains(&node.trace.address)).collect()
+}
+
+/// Runs `identify` only on the subset of `nodes` that aren't already cached (and not expired),
+/// caching its results for next time. Nodes already present in `cache` are reconstructed from
+/// their cached entry without calling `identify` at all.
+fn identify_with_cache<'b>(
+    nodes: &[&'b CallTraceNode],
+    cache: Option<&mut IdentifiedAddressCache>,
+    chain_of: impl Fn(&CallTraceNode) -> Option<Chain>,
+    identify: impl FnOnce(&[&'b CallTraceNode]) -> Vec<IdentifiedAddress<'b>>,
+) -> Vec<IdentifiedAddress<'b>> {
+    let Some(cache) = cache else { return identify(nodes) };
+
+    let mut hits = Vec::new();
+    let mut misses = Vec::new();
+    let mut chain_by_address = HashMap::default();
+    for &node in nodes {
+        let address = node.trace.address;
+        let chain = chain_of(node);
+        chain_by_address.insert(address, chain);
+        match cache.get(chain, address) {
+            Some(cached) => hits.push(IdentifiedAddress {
+                address,
+                label: cached.label,
+                contract: cached.contract,
+                abi: cached.abi.map(Cow::Owned),
+                artifact_id: None,
+                language: cached.language,
+                sources: None,
+            }),
+            None => misses.push(node),
+        }
+    }
+
+    let identified = identify(&misses);
+    for identity in &identified {
+        let chain = chain_by_address.get(&identity.address).copied().flatten();
+        cache.insert(
+            chain,
+            identity.address,
+            identity.label.clone(),
+            identity.contract.clone(),
+            identity.abi.as_deref().cloned(),
+            identity.language,
+        );
+    }
+
+    hits.into_iter().chain(identified).collect()
+}
+
 impl<'a> TraceIdentifiers<'a> {
     /// Creates a new, empty instance.
-    pub const fn new() -> Self {
-        Self { local: None, etherscan: None }
+    pub fn new() -> Self {
+        Self {
+            local: None,
+            etherscan: None,
+            etherscan_chains: HashMap::default(),
+            chain_resolver: None,
+            sourcify: None,
+            cache: None,
+            fetch_sources: false,
+        }
+    }
+
+    /// Routes each node to the Etherscan identifier for its chain (as resolved by
+    /// `chain_resolver`), falling back to the default `etherscan` client for nodes with no
+    /// chain hint or no matching per-chain client.
+    fn identify_addresses_etherscan<'b>(&mut self, nodes: &[&'b CallTraceNode]) -> Vec<IdentifiedAddress<'b>> {
+        let mut cache = self.cache.take();
+
+        let result = if self.etherscan_chains.is_empty() {
+            match &mut self.etherscan {
+                Some(etherscan) => identify_with_cache(nodes, cache.as_mut(), |_| None, |nodes| {
+                    etherscan.identify_addresses(nodes)
+                }),
+                None => Vec::new(),
+            }
+        } else {
+            let mut by_chain: HashMap<Option<Chain>, Vec<&CallTraceNode>> = HashMap::default();
+            for &node in nodes {
+                let chain = self.chain_resolver.as_ref().and_then(|resolve| resolve(node));
+                by_chain.entry(chain).or_default().push(node);
+            }
+
+            // `HashMap` iteration order is unspecified, which would otherwise make the emitted
+            // identity order (and therefore e.g. displayed trace output) nondeterministic across
+            // runs; iterate chains in a stable order instead.
+            let mut chains: Vec<_> = by_chain.keys().copied().collect();
+            chains.sort_by_key(|chain| chain.map(|chain| chain.id()));
+
+            let mut identities = Vec::with_capacity(nodes.len());
+            for chain in chains {
+                let chain_nodes = by_chain.remove(&chain).unwrap_or_default();
+                let identifier = chain
+                    .and_then(|chain| self.etherscan_chains.get_mut(&chain))
+                    .or(self.etherscan.as_mut());
+                if let Some(identifier) = identifier {
+                    identities.extend(identify_with_cache(
+                        &chain_nodes,
+                        cache.as_mut(),
+                        |_| chain,
+                        |nodes| identifier.identify_addresses(nodes),
+                    ));
+                }
+            }
+            identities
+        };
+
+        self.cache = cache;
+        result
     }
 
     /// Sets the local identifier.
@@ -93,11 +301,91 @@ impl<'a> TraceIdentifiers<'a> {
     /// Sets the etherscan identifier.
     pub fn with_etherscan(mut self, config: &Config, chain: Option<Chain>) -> eyre::Result<Self> {
         self.etherscan = EtherscanIdentifier::new(config, chain)?;
+        if let Some(etherscan) = &mut self.etherscan {
+            etherscan.set_fetch_sources(self.fetch_sources);
+        }
+        Ok(self)
+    }
+
+    /// Adds per-chain Etherscan identifiers for each of `chains`, used to correctly label
+    /// traces produced by multi-fork / cross-chain scripts.
+    ///
+    /// Call [`Self::with_etherscan_resolver`] (or set [`Self::chain_resolver`] directly) to
+    /// tell `identify_addresses` which chain each node belongs to; nodes with no resolved chain
+    /// fall back to the default `etherscan` client, if any.
+    pub fn with_etherscan_chains(mut self, config: &Config, chains: &[Chain]) -> eyre::Result<Self> {
+        for &chain in chains {
+            if let Some(mut identifier) = EtherscanIdentifier::new(config, Some(chain))? {
+                identifier.set_fetch_sources(self.fetch_sources);
+                self.etherscan_chains.insert(chain, identifier);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Sets the closure used to resolve which chain a [`CallTraceNode`] belongs to, for routing
+    /// to the per-chain Etherscan identifiers set via [`Self::with_etherscan_chains`].
+    pub fn with_etherscan_resolver(
+        mut self,
+        resolver: impl Fn(&CallTraceNode) -> Option<Chain> + 'a,
+    ) -> Self {
+        self.chain_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Sets the Sourcify identifier.
+    pub fn with_sourcify(mut self, config: &Config, chain: Option<Chain>) -> eyre::Result<Self> {
+        self.sourcify = SourcifyIdentifier::new(config, chain)?;
+        if let Some(sourcify) = &mut self.sourcify {
+            sourcify.set_fetch_sources(self.fetch_sources);
+        }
         Ok(self)
     }
 
+    /// Toggles fetching the full verified source tree for remote addresses (see
+    /// [`IdentifiedAddress::sources`]).
+    ///
+    /// Most callers only need the ABI and label, so this defaults to `false`; pass `true` if you
+    /// need source-level trace annotations (e.g. which file/line a revert came from) for
+    /// contracts that aren't compiled locally.
+    pub fn with_sources(mut self, fetch_sources: bool) -> Self {
+        self.fetch_sources = fetch_sources;
+        if let Some(etherscan) = &mut self.etherscan {
+            etherscan.set_fetch_sources(fetch_sources);
+        }
+        for etherscan in self.etherscan_chains.values_mut() {
+            etherscan.set_fetch_sources(fetch_sources);
+        }
+        if let Some(sourcify) = &mut self.sourcify {
+            sourcify.set_fetch_sources(fetch_sources);
+        }
+        self
+    }
+
+    /// Enables the persistent, TTL-aware on-disk cache for identified addresses, backed by a
+    /// JSON file at `path`. Entries older than `ttl` are treated as a cache miss and re-fetched.
+    pub fn with_cache(mut self, path: impl Into<std::path::PathBuf>, ttl: Duration) -> Self {
+        self.cache = Some(IdentifiedAddressCache::load(path, ttl));
+        self
+    }
+
+    /// Identifies only the subset of `nodes` matching `filter`, skipping the rest entirely.
+    ///
+    /// This is useful for large traces where running Etherscan/Sourcify lookups on every
+    /// address would be wasteful, e.g. when only external top-level calls are of interest.
+    pub fn identify_filtered(
+        &mut self,
+        nodes: &[&CallTraceNode],
+        filter: &TraceFilter,
+    ) -> Vec<IdentifiedAddress<'_>> {
+        self.identify_addresses(&filter.filter(nodes))
+    }
+
     /// Returns `true` if there are no set identifiers.
     pub fn is_empty(&self) -> bool {
-        self.local.is_none() && self.etherscan.is_none()
+        self.local.is_none()
+            && self.etherscan.is_none()
+            && self.etherscan_chains.is_empty()
+            && self.sourcify.is_none()
     }
 }
@@ -0,0 +1,200 @@
+use super::{IdentifiedAddress, SourceCodeLanguage, SourceTree, TraceIdentifier};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{Address, map::HashMap};
+use foundry_config::{Chain, Config};
+use revm_inspectors::tracing::types::CallTraceNode;
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// The base URL of the Sourcify repository used to fetch verified contract metadata.
+const SOURCIFY_REPO_URL: &str = "https://repo.sourcify.dev";
+
+/// A [`TraceIdentifier`] that queries the [Sourcify](https://sourcify.dev) verified-contract
+/// repository for addresses that could not be identified locally or via Etherscan.
+///
+/// Unlike [`EtherscanIdentifier`](super::EtherscanIdentifier), this does not require an API key,
+/// so it acts as a fallback source of ABI recovery for chains and contracts Etherscan doesn't
+/// cover.
+#[derive(Clone, Debug)]
+pub struct SourcifyIdentifier {
+    /// The chain to query Sourcify for.
+    chain: Chain,
+    /// Client used to query Sourcify's HTTP API.
+    client: reqwest::Client,
+    /// Cache of already fetched (or failed) addresses, so repeated lookups within a run don't
+    /// re-hit the network.
+    cache: HashMap<Address, Option<SourcifyMetadata>>,
+    /// Whether to additionally fetch the full verified source tree for each address.
+    fetch_sources: bool,
+}
+
+#[derive(Clone, Debug)]
+struct SourcifyMetadata {
+    abi: JsonAbi,
+    /// Path to the compiled source file, e.g. `"src/Token.sol"`.
+    artifact_path: String,
+    contract_name: String,
+    language: Option<SourceCodeLanguage>,
+    compiler_version: Option<String>,
+    settings: Option<serde_json::Value>,
+    sources: Option<HashMap<String, String>>,
+}
+
+/// Subset of the fields Sourcify returns in `metadata.json` that we care about.
+#[derive(Deserialize)]
+struct RawMetadata {
+    /// Either `"Solidity"` or `"Vyper"`.
+    language: Option<String>,
+    compiler: RawCompiler,
+    output: RawOutput,
+    settings: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct RawCompiler {
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawOutput {
+    abi: JsonAbi,
+}
+
+impl SourcifyIdentifier {
+    /// Returns the chain this identifier queries Sourcify for.
+    pub fn chain(&self) -> Chain {
+        self.chain
+    }
+
+    /// Creates a new Sourcify identifier for the given chain.
+    ///
+    /// Returns `None` if `chain` could not be resolved from `config`.
+    pub fn new(config: &Config, chain: Option<Chain>) -> eyre::Result<Option<Self>> {
+        let Some(chain) = chain.or(config.chain) else { return Ok(None) };
+        Ok(Some(Self {
+            chain,
+            client: reqwest::Client::new(),
+            cache: HashMap::default(),
+            fetch_sources: false,
+        }))
+    }
+
+    /// Toggles fetching the full verified source tree alongside the ABI and label.
+    pub fn set_fetch_sources(&mut self, fetch_sources: bool) {
+        self.fetch_sources = fetch_sources;
+    }
+
+    /// Fetches and caches the verified metadata for `address`, trying a full match first and
+    /// falling back to a partial match.
+    fn fetch(&mut self, address: Address) -> Option<SourcifyMetadata> {
+        if let Some(entry) = self.cache.get(&address) {
+            return entry.clone();
+        }
+
+        let metadata = self
+            .fetch_match(address, "full_match")
+            .or_else(|| self.fetch_match(address, "partial_match"));
+        self.cache.insert(address, metadata.clone());
+        metadata
+    }
+
+    fn fetch_match(&self, address: Address, match_kind: &str) -> Option<SourcifyMetadata> {
+        let url = format!(
+            "{SOURCIFY_REPO_URL}/contracts/{match_kind}/{}/{address:?}/metadata.json",
+            self.chain.id()
+        );
+        let raw: RawMetadata = foundry_common::block_on(async {
+            self.client.get(&url).send().await?.error_for_status()?.json().await
+        })
+        .ok()?;
+
+        // `compilationTarget` maps the compiled file's path to the contract name verified from
+        // it, e.g. `{"src/Token.sol": "Token"}`; use the path as the artifact segment of the
+        // `"<artifact>:<contract>"` identifier, instead of duplicating the contract name.
+        let compilation_target = raw
+            .settings
+            .get("compilationTarget")
+            .and_then(|target| target.as_object())
+            .and_then(|target| target.iter().next());
+        let artifact_path =
+            compilation_target.map(|(path, _)| path.clone()).unwrap_or_else(|| "Unknown".into());
+        let contract_name = compilation_target
+            .and_then(|(_, name)| name.as_str())
+            .unwrap_or("Unknown")
+            .to_string();
+
+        let language = match raw.language.as_deref() {
+            Some("Solidity") => Some(SourceCodeLanguage::Solidity),
+            Some("Vyper") => Some(SourceCodeLanguage::Vyper),
+            _ => None,
+        };
+
+        let sources =
+            if self.fetch_sources { self.fetch_sources_tree(address, match_kind) } else { None };
+
+        Some(SourcifyMetadata {
+            abi: raw.output.abi,
+            artifact_path,
+            contract_name,
+            language,
+            compiler_version: raw.compiler.version,
+            settings: Some(raw.settings),
+            sources,
+        })
+    }
+
+    /// Fetches every verified source file for `address`, keyed by its path relative to the
+    /// compilation root.
+    fn fetch_sources_tree(&self, address: Address, match_kind: &str) -> Option<HashMap<String, String>> {
+        let list_url = format!(
+            "{SOURCIFY_REPO_URL}/files/tree/any/{}/{address:?}",
+            self.chain.id()
+        );
+        let urls: Vec<String> = foundry_common::block_on(async {
+            self.client.get(&list_url).send().await?.error_for_status()?.json().await
+        })
+        .ok()?;
+
+        let prefix = format!("/{match_kind}/{}/{address:?}/", self.chain.id());
+        let mut sources = HashMap::default();
+        for url in urls {
+            let Some(path) = url.split(&prefix).nth(1) else { continue };
+            let Ok(content) = foundry_common::block_on(async {
+                self.client.get(&url).send().await?.error_for_status()?.text().await
+            }) else {
+                continue;
+            };
+            sources.insert(path.to_string(), content);
+        }
+        Some(sources)
+    }
+}
+
+impl TraceIdentifier for SourcifyIdentifier {
+    fn identify_addresses(&mut self, nodes: &[&CallTraceNode]) -> Vec<IdentifiedAddress<'_>> {
+        nodes
+            .iter()
+            .filter_map(|node| {
+                let address = node.trace.address;
+                let metadata = self.fetch(address)?;
+                // Normalize into the `"<artifact>:<contract>"` convention used elsewhere, using
+                // the compiled file path (from `compilationTarget`) as the artifact segment.
+                let contract = format!("{}:{}", metadata.artifact_path, metadata.contract_name);
+                let sources = metadata.sources.map(|sources| SourceTree {
+                    sources,
+                    compiler_version: metadata.compiler_version.clone(),
+                    settings: metadata.settings.clone(),
+                });
+                Some(IdentifiedAddress {
+                    address,
+                    label: Some(metadata.contract_name.clone()),
+                    contract: Some(contract),
+                    abi: Some(Cow::Owned(metadata.abi)),
+                    artifact_id: None,
+                    language: metadata.language,
+                    sources,
+                })
+            })
+            .collect()
+    }
+}